@@ -6,25 +6,172 @@ use arrow::record_batch::RecordBatch;
 use clap::{Parser, ValueHint};
 use parquet::{
     arrow::ArrowWriter,
-    basic::{Compression, Encoding},
-    file::properties::{EnabledStatistics, WriterProperties},
+    basic::{BrotliLevel, Compression, Encoding, GzipLevel, ZstdLevel},
+    file::properties::{EnabledStatistics, WriterProperties, WriterVersion},
+    schema::types::ColumnPath,
 };
+use regex::Regex;
 use serde_json::{to_string_pretty, Value};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-#[derive(clap::ArgEnum, Clone)]
-#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
-enum ParquetCompression {
-    UNCOMPRESSED,
-    SNAPPY,
-    GZIP,
-    LZO,
-    BROTLI,
-    LZ4,
-    ZSTD,
+/// An input that is either a regular file or a buffered copy of stdin's contents.
+/// Stdin is read eagerly into memory so it can be seeked during schema inference,
+/// the same way a file on disk can.
+enum Input {
+    File(File),
+    Stdin(Cursor<Vec<u8>>),
+}
+
+impl Input {
+    fn open(path: &PathBuf) -> io::Result<Self> {
+        if path.as_os_str() == "-" {
+            let mut buf = Vec::new();
+            io::stdin().read_to_end(&mut buf)?;
+            Ok(Input::Stdin(Cursor::new(buf)))
+        } else {
+            Ok(Input::File(File::open(path)?))
+        }
+    }
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Input::File(file) => file.read(buf),
+            Input::Stdin(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for Input {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Input::File(file) => file.seek(pos),
+            Input::Stdin(cursor) => cursor.seek(pos),
+        }
+    }
+}
+
+/// An output that is either a regular file or stdout.
+enum Output {
+    File(File),
+    Stdout(io::Stdout),
+}
+
+impl Output {
+    fn create(path: &PathBuf) -> io::Result<Self> {
+        if path.as_os_str() == "-" {
+            Ok(Output::Stdout(io::stdout()))
+        } else {
+            Ok(Output::File(File::create(path)?))
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::File(file) => file.write(buf),
+            Output::Stdout(stdout) => stdout.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::File(file) => file.flush(),
+            Output::Stdout(stdout) => stdout.flush(),
+        }
+    }
+}
+
+/// Parses a compression codec name with an optional level suffix, e.g. `zstd(9)`,
+/// `gzip(6)`, `brotli(11)`. Without a suffix, `gzip`, `brotli` and `zstd` use their
+/// default level. Codecs without a configurable level (`uncompressed`, `snappy`,
+/// `lzo`, `lz4`) accept a suffix but ignore it.
+fn parse_compression(s: &str) -> Result<Compression, String> {
+    let (name, level) = match s.find('(') {
+        Some(start) => {
+            let rest = s[start + 1..]
+                .strip_suffix(')')
+                .ok_or_else(|| format!("invalid compression level syntax: {}", s))?;
+            let level: i32 = rest
+                .parse()
+                .map_err(|_| format!("invalid compression level: {}", rest))?;
+            (&s[..start], Some(level))
+        }
+        None => (s, None),
+    };
+
+    match name.to_ascii_uppercase().as_str() {
+        "UNCOMPRESSED" => Ok(Compression::UNCOMPRESSED),
+        "SNAPPY" => Ok(Compression::SNAPPY),
+        "GZIP" => {
+            let level = match level {
+                Some(level) => GzipLevel::try_new(level as u32).map_err(|err| err.to_string())?,
+                None => GzipLevel::default(),
+            };
+            Ok(Compression::GZIP(level))
+        }
+        "LZO" => Ok(Compression::LZO),
+        "BROTLI" => {
+            let level = match level {
+                Some(level) => {
+                    BrotliLevel::try_new(level as u32).map_err(|err| err.to_string())?
+                }
+                None => BrotliLevel::default(),
+            };
+            Ok(Compression::BROTLI(level))
+        }
+        "LZ4" => Ok(Compression::LZ4),
+        "ZSTD" => {
+            let level = match level {
+                Some(level) => ZstdLevel::try_new(level).map_err(|err| err.to_string())?,
+                None => ZstdLevel::default(),
+            };
+            Ok(Compression::ZSTD(level))
+        }
+        other => Err(format!("unknown compression codec: {}", other)),
+    }
+}
+
+/// Splits a `name:value` per-column override argument into its column name and value.
+fn split_column_arg(s: &str) -> Result<(&str, &str), String> {
+    s.split_once(':')
+        .ok_or_else(|| format!("expected `name:value`, got: {}", s))
+}
+
+fn parse_encoding(s: &str) -> Result<Encoding, String> {
+    match s.to_ascii_uppercase().as_str() {
+        "PLAIN" => Ok(Encoding::PLAIN),
+        "RLE" => Ok(Encoding::RLE),
+        "BIT_PACKED" => Ok(Encoding::BIT_PACKED),
+        "DELTA_BINARY_PACKED" => Ok(Encoding::DELTA_BINARY_PACKED),
+        "DELTA_LENGTH_BYTE_ARRAY" => Ok(Encoding::DELTA_LENGTH_BYTE_ARRAY),
+        "DELTA_BYTE_ARRAY" => Ok(Encoding::DELTA_BYTE_ARRAY),
+        "RLE_DICTIONARY" => Ok(Encoding::RLE_DICTIONARY),
+        other => Err(format!("unknown encoding: {}", other)),
+    }
+}
+
+fn parse_column_encoding(s: &str) -> Result<(String, Encoding), String> {
+    let (name, value) = split_column_arg(s)?;
+    Ok((name.to_string(), parse_encoding(value)?))
+}
+
+fn parse_column_compression(s: &str) -> Result<(String, Compression), String> {
+    let (name, value) = split_column_arg(s)?;
+    Ok((name.to_string(), parse_compression(value)?))
+}
+
+fn parse_column_dictionary(s: &str) -> Result<(String, bool), String> {
+    let (name, value) = split_column_arg(s)?;
+    let enabled = value
+        .parse()
+        .map_err(|_| format!("invalid dictionary flag: {}", value))?;
+    Ok((name.to_string(), enabled))
 }
 
 #[derive(clap::ArgEnum, Clone)]
@@ -47,16 +194,29 @@ enum ParquetEnabledStatistics {
     Page,
 }
 
+#[derive(clap::ArgEnum, Clone)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+enum ParquetWriterVersion {
+    V1_0,
+    V2_0,
+}
+
 #[derive(Parser)]
 #[clap(version = env!("CARGO_PKG_VERSION"), author = "Dominik Moritz <domoritz@cmu.edu>")]
 struct Opts<I: clap::Args, O: clap::Args> {
-    /// Input file.
-    #[clap(name = "INPUT", parse(from_os_str), value_hint = ValueHint::AnyPath)]
-    input: PathBuf,
-
-    /// Output file.
-    #[clap(name = "OUTPUT", parse(from_os_str), value_hint = ValueHint::AnyPath)]
-    output: PathBuf,
+    /// Input file(s), optionally followed by the output file as the final
+    /// positional argument, e.g. `csv2parquet in.csv out.parquet` or
+    /// `csv2parquet a.csv b.csv out.parquet`. When `-o`/`--output` is given
+    /// instead, every positional here is treated as an input. Use `-` for
+    /// stdin (as an input) or stdout (as the output).
+    #[clap(name = "INPUT", required = true, min_values = 1, parse(from_os_str), value_hint = ValueHint::AnyPath)]
+    input: Vec<PathBuf>,
+
+    /// Output file. Defaults to the last positional argument in `INPUT`; set
+    /// this instead if every positional should be treated as an input.
+    /// Use `-` to write to stdout.
+    #[clap(short = 'o', long, parse(from_os_str), value_hint = ValueHint::AnyPath)]
+    output: Option<PathBuf>,
 
     /// File with Arrow schema in JSON format.
     #[clap(short = 's', long, parse(from_os_str), value_hint = ValueHint::AnyPath)]
@@ -83,6 +243,30 @@ struct Opts<I: clap::Args, O: clap::Args> {
     dry: bool,
 }
 
+impl<I: clap::Args, O: clap::Args> Opts<I, O> {
+    /// Splits `input` into the files to read and the file to write. When
+    /// `-o`/`--output` is given, every positional in `input` is an input file;
+    /// otherwise the last positional is taken as the output, preserving the
+    /// original `csv2parquet INPUT OUTPUT` invocation now that `input` is variadic.
+    fn resolve_paths(&self) -> Result<(Vec<PathBuf>, PathBuf), ArrowError> {
+        match &self.output {
+            Some(output) => Ok((self.input.clone(), output.clone())),
+            None => {
+                let mut paths = self.input.clone();
+                if paths.len() < 2 {
+                    return Err(ArrowError::InvalidArgumentError(
+                        "an output file is required: pass it as the last positional argument \
+                         or via -o/--output"
+                            .to_string(),
+                    ));
+                }
+                let output = paths.pop().unwrap();
+                Ok((paths, output))
+            }
+        }
+    }
+}
+
 #[derive(clap::Args)]
 pub struct CsvOpts {
     /// Set whether the CSV file has headers
@@ -92,6 +276,23 @@ pub struct CsvOpts {
     /// Set the CSV file's column delimiter as a byte character.
     #[clap(short, long, default_value = ",")]
     delimiter: char,
+
+    /// Set the character used for quoting fields.
+    #[clap(long)]
+    quote: Option<char>,
+
+    /// Set the character used for escaping quotes inside a quoted field.
+    #[clap(long)]
+    escape: Option<char>,
+
+    /// Set a regex that matches values which should be treated as null.
+    #[clap(long)]
+    null_regex: Option<String>,
+
+    /// Only read these columns, in this order. Accepts a comma-separated list and
+    /// can be repeated.
+    #[clap(long, alias = "columns", use_value_delimiter = true)]
+    projection: Vec<String>,
 }
 
 #[derive(clap::Args)]
@@ -99,9 +300,14 @@ pub struct JsonOpts;
 
 #[derive(clap::Args)]
 pub struct ParquetOpts {
-    /// Set the compression.
-    #[clap(short, long, arg_enum)]
-    compression: Option<ParquetCompression>,
+    /// Sets the Parquet format version. Version 2.0 unlocks the DELTA_*
+    /// encodings but may not be readable by all engines.
+    #[clap(long, arg_enum)]
+    writer_version: Option<ParquetWriterVersion>,
+
+    /// Set the compression, optionally with a level, e.g. `zstd(9)`, `gzip(6)`, `brotli(11)`.
+    #[clap(short, long, parse(try_from_str = parse_compression))]
+    compression: Option<Compression>,
 
     /// Sets encoding for any column.
     #[clap(short, long, arg_enum)]
@@ -138,6 +344,34 @@ pub struct ParquetOpts {
     /// Sets max statistics size for any column. Applicable only if statistics are enabled.
     #[clap(long)]
     max_statistics_size: Option<usize>,
+
+    /// Enables bloom filters for all columns.
+    #[clap(long)]
+    bloom_filter: bool,
+
+    /// Sets the target false positive probability for bloom filters.
+    #[clap(long)]
+    bloom_filter_fpp: Option<f64>,
+
+    /// Sets the number of distinct values to size bloom filters for.
+    #[clap(long)]
+    bloom_filter_ndv: Option<u64>,
+
+    /// Enables a bloom filter for this column specifically. Can be repeated.
+    #[clap(long)]
+    bloom_filter_column: Vec<String>,
+
+    /// Overrides the encoding for a specific column, e.g. `amount:DELTA_BINARY_PACKED`. Can be repeated.
+    #[clap(long, parse(try_from_str = parse_column_encoding))]
+    column_encoding: Vec<(String, Encoding)>,
+
+    /// Overrides the compression for a specific column, e.g. `amount:zstd(9)`. Can be repeated.
+    #[clap(long, parse(try_from_str = parse_column_compression))]
+    column_compression: Vec<(String, Compression)>,
+
+    /// Overrides dictionary encoding for a specific column, e.g. `amount:false`. Can be repeated.
+    #[clap(long, parse(try_from_str = parse_column_dictionary))]
+    column_dictionary: Vec<(String, bool)>,
 }
 
 pub fn run<I, O>() -> Result<(), ArrowError>
@@ -149,8 +383,19 @@ where
     O: OutputFormat,
 {
     let opts: Opts<I, O> = Opts::parse();
+    let (input_paths, output_path) = opts.resolve_paths()?;
 
-    let mut input = File::open(&opts.input.as_path())?;
+    if opts.schema_file.is_some() {
+        opts.input_format.validate_schema_file_compatibility()?;
+    }
+
+    // Open every input exactly once. Stdin ("-") can only be drained a single
+    // time, so each path's `Input` must be reused for both schema inference
+    // and reading rather than re-opened later.
+    let mut inputs = input_paths
+        .iter()
+        .map(Input::open)
+        .collect::<io::Result<Vec<_>>>()?;
 
     let schema = match &opts.schema_file {
         Some(schema_def_file_path) => {
@@ -176,7 +421,7 @@ where
         _ => {
             match opts
                 .input_format
-                .infer_file_schema(opts.max_read_records, &mut input)
+                .infer_file_schema(opts.max_read_records, &mut inputs[0])
             {
                 Ok(schema) => Ok(schema),
                 Err(error) => Err(ArrowError::SchemaError(format!(
@@ -198,16 +443,43 @@ where
 
     let schema_ref = Arc::new(schema);
 
-    let reader = opts.input_format.make_reader(schema_ref.clone(), input)?;
+    // Validate every remaining input's schema *before* creating the writer (and
+    // thus the output file) so a mismatch on a later file is reported without
+    // having written any output. `schema_ref` was inferred from `inputs[0]`
+    // above, so re-inferring and comparing it against itself would be a no-op.
+    if opts.schema_file.is_none() {
+        for (input_path, input) in input_paths.iter().zip(inputs.iter_mut()).skip(1) {
+            let inferred = opts
+                .input_format
+                .infer_file_schema(opts.max_read_records, input)
+                .map_err(|error| {
+                    ArrowError::SchemaError(format!("Error inferring schema: {}", error))
+                })?;
+            if inferred != *schema_ref {
+                return Err(ArrowError::SchemaError(format!(
+                    "Schema of {:?} does not match the schema inferred from {:?}",
+                    input_path, input_paths[0]
+                )));
+            }
+        }
+    }
 
-    let output = File::create(opts.output)?;
+    let output = Output::create(&output_path)?;
 
-    let mut writer = opts.output_format.try_new_writer(output, schema_ref)?;
+    let mut writer = opts
+        .output_format
+        .try_new_writer(output, schema_ref.clone())?;
 
-    for batch in reader {
-        match batch {
-            Ok(batch) => opts.output_format.write(&mut writer, &batch)?,
-            Err(error) => return Err(error),
+    for input in inputs {
+        let reader =
+            opts.input_format
+                .make_reader(schema_ref.clone(), opts.max_read_records, input)?;
+
+        for batch in reader {
+            match batch {
+                Ok(batch) => opts.output_format.write(&mut writer, &batch)?,
+                Err(error) => return Err(error),
+            }
         }
     }
 
@@ -223,53 +495,122 @@ pub trait InputFormat {
     fn infer_file_schema(
         &self,
         max_read_records: Option<usize>,
-        input: &mut File,
+        input: &mut Input,
     ) -> arrow::error::Result<Schema>;
 
     fn make_reader(
         &self,
         schema_ref: Arc<Schema>,
-        input: File,
+        max_read_records: Option<usize>,
+        input: Input,
     ) -> arrow::error::Result<Self::Reader>;
+
+    /// Called when `--schema-file` is supplied, bypassing schema inference.
+    /// Implementations whose `make_reader` derives its behavior from the inferred
+    /// schema (e.g. column projection) should reject themselves here if they can't
+    /// honor that option against an externally supplied schema.
+    fn validate_schema_file_compatibility(&self) -> arrow::error::Result<()> {
+        Ok(())
+    }
 }
 
 impl InputFormat for CsvOpts {
-    type Reader = csv::Reader<File>;
+    type Reader = csv::Reader<Input>;
 
     fn infer_file_schema(
         &self,
         max_read_records: Option<usize>,
-        input: &mut File,
+        input: &mut Input,
     ) -> arrow::error::Result<Schema> {
-        csv::reader::infer_file_schema(
+        let (schema, _) = csv::reader::infer_file_schema(
             input,
             self.delimiter as u8,
             max_read_records,
             self.header.unwrap_or(true),
-        )
-        .map(|(s, _)| s)
+        )?;
+
+        if self.projection.is_empty() {
+            Ok(schema)
+        } else {
+            let indices = self
+                .projection
+                .iter()
+                .map(|name| schema.index_of(name))
+                .collect::<arrow::error::Result<Vec<_>>>()?;
+            schema.project(&indices)
+        }
     }
 
     fn make_reader(
         &self,
         schema_ref: Arc<Schema>,
-        input: File,
-    ) -> arrow::error::Result<csv::Reader<File>> {
-        let builder = csv::ReaderBuilder::new()
+        max_read_records: Option<usize>,
+        mut input: Input,
+    ) -> arrow::error::Result<csv::Reader<Input>> {
+        let mut builder = csv::ReaderBuilder::new()
             .has_header(self.header.unwrap_or(true))
-            .with_delimiter(self.delimiter as u8)
-            .with_schema(schema_ref);
+            .with_delimiter(self.delimiter as u8);
+
+        if let Some(quote) = self.quote {
+            builder = builder.with_quote(quote as u8);
+        }
+
+        if let Some(escape) = self.escape {
+            builder = builder.with_escape(escape as u8);
+        }
+
+        if let Some(null_regex) = &self.null_regex {
+            let null_regex = Regex::new(null_regex)
+                .map_err(|err| ArrowError::ParseError(err.to_string()))?;
+            builder = builder.with_null_regex(null_regex);
+        }
+
+        if self.projection.is_empty() {
+            builder = builder.with_schema(schema_ref);
+        } else {
+            // `with_projection` indexes into the full, unprojected column set, so
+            // re-infer it from the file rather than reusing the already-projected
+            // `schema_ref`. Use the same `max_read_records` as the original
+            // inference so this sees the same sample and derives the same types
+            // -- otherwise a column sampled differently here than in `run()`
+            // could disagree with the writer's schema.
+            let (full_schema, _) = csv::reader::infer_file_schema(
+                &mut input,
+                self.delimiter as u8,
+                max_read_records,
+                self.header.unwrap_or(true),
+            )?;
+            let indices = self
+                .projection
+                .iter()
+                .map(|name| full_schema.index_of(name))
+                .collect::<arrow::error::Result<Vec<_>>>()?;
+            builder = builder
+                .with_schema(Arc::new(full_schema))
+                .with_projection(indices);
+        }
+
         builder.build(input)
     }
+
+    fn validate_schema_file_compatibility(&self) -> arrow::error::Result<()> {
+        if self.projection.is_empty() {
+            Ok(())
+        } else {
+            Err(ArrowError::InvalidArgumentError(
+                "--projection/--columns cannot be combined with --schema-file".to_string(),
+            ))
+        }
+    }
 }
 
 impl InputFormat for JsonOpts {
-    type Reader = json::Reader<File>;
+    type Reader = json::Reader<Input>;
 
     fn infer_file_schema(
         &self,
         max_read_records: Option<usize>,
-        input: &mut File,
+        input: &mut Input,
     ) -> arrow::error::Result<Schema> {
         let mut buf = BufReader::new(input);
         json::reader::infer_json_schema_from_seekable(&mut buf, max_read_records)
@@ -278,7 +619,8 @@ impl InputFormat for JsonOpts {
     fn make_reader(
         &self,
         schema_ref: Arc<Schema>,
-        input: File,
+        _max_read_records: Option<usize>,
+        input: Input,
     ) -> arrow::error::Result<Self::Reader> {
         let builder = json::ReaderBuilder::new().with_schema(schema_ref);
         builder.build(input)
@@ -290,7 +632,7 @@ pub trait OutputFormat {
 
     fn try_new_writer(
         &self,
-        output: File,
+        output: Output,
         schema_ref: Arc<Schema>,
     ) -> arrow::error::Result<Self::Writer>;
 
@@ -300,15 +642,24 @@ pub trait OutputFormat {
 }
 
 impl OutputFormat for ParquetOpts {
-    type Writer = ArrowWriter<File>;
+    type Writer = ArrowWriter<Output>;
 
     fn try_new_writer(
         &self,
-        output: File,
+        output: Output,
         schema_ref: Arc<Schema>,
     ) -> arrow::error::Result<Self::Writer> {
         let mut props = WriterProperties::builder().set_dictionary_enabled(self.dictionary);
 
+        if let Some(writer_version) = &self.writer_version {
+            let writer_version = match writer_version {
+                ParquetWriterVersion::V1_0 => WriterVersion::PARQUET_1_0,
+                ParquetWriterVersion::V2_0 => WriterVersion::PARQUET_2_0,
+            };
+
+            props = props.set_writer_version(writer_version);
+        }
+
         if let Some(statistics) = &self.statistics {
             let statistics = match statistics {
                 ParquetEnabledStatistics::Chunk => EnabledStatistics::Chunk,
@@ -319,17 +670,7 @@ impl OutputFormat for ParquetOpts {
             props = props.set_statistics_enabled(statistics);
         }
 
-        if let Some(compression) = &self.compression {
-            let compression = match compression {
-                ParquetCompression::UNCOMPRESSED => Compression::UNCOMPRESSED,
-                ParquetCompression::SNAPPY => Compression::SNAPPY,
-                ParquetCompression::GZIP => Compression::GZIP,
-                ParquetCompression::LZO => Compression::LZO,
-                ParquetCompression::BROTLI => Compression::BROTLI,
-                ParquetCompression::LZ4 => Compression::LZ4,
-                ParquetCompression::ZSTD => Compression::ZSTD,
-            };
-
+        if let Some(compression) = self.compression {
             props = props.set_compression(compression);
         }
 
@@ -375,6 +716,34 @@ impl OutputFormat for ParquetOpts {
             props = props.set_max_statistics_size(size);
         }
 
+        if self.bloom_filter {
+            props = props.set_bloom_filter_enabled(true);
+        }
+
+        if let Some(fpp) = self.bloom_filter_fpp {
+            props = props.set_bloom_filter_fpp(fpp);
+        }
+
+        if let Some(ndv) = self.bloom_filter_ndv {
+            props = props.set_bloom_filter_ndv(ndv);
+        }
+
+        for name in &self.bloom_filter_column {
+            props = props.set_column_bloom_filter_enabled(ColumnPath::from(name.clone()), true);
+        }
+
+        for (name, encoding) in &self.column_encoding {
+            props = props.set_column_encoding(ColumnPath::from(name.clone()), *encoding);
+        }
+
+        for (name, compression) in &self.column_compression {
+            props = props.set_column_compression(ColumnPath::from(name.clone()), *compression);
+        }
+
+        for (name, enabled) in &self.column_dictionary {
+            props = props.set_column_dictionary_enabled(ColumnPath::from(name.clone()), *enabled);
+        }
+
         ArrowWriter::try_new(output, schema_ref, Some(props.build())).map_err(|err| err.into())
     }
 
@@ -386,3 +755,264 @@ impl OutputFormat for ParquetOpts {
         writer.close().map(|_| ()).map_err(|err| err.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+
+    fn parquet_opts() -> ParquetOpts {
+        ParquetOpts {
+            writer_version: None,
+            compression: None,
+            encoding: None,
+            data_pagesize_limit: None,
+            dictionary_pagesize_limit: None,
+            write_batch_size: None,
+            max_row_group_size: None,
+            created_by: None,
+            dictionary: false,
+            statistics: None,
+            max_statistics_size: None,
+            bloom_filter: false,
+            bloom_filter_fpp: None,
+            bloom_filter_ndv: None,
+            bloom_filter_column: Vec::new(),
+            column_encoding: Vec::new(),
+            column_compression: Vec::new(),
+            column_dictionary: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn writer_version_flag_is_applied_to_the_writer() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let mut opts = parquet_opts();
+        opts.writer_version = Some(ParquetWriterVersion::V2_0);
+
+        let path = std::env::temp_dir().join("csv2parquet_test_writer_version.parquet");
+        let writer = opts
+            .try_new_writer(Output::create(&path).unwrap(), schema)
+            .unwrap();
+        let metadata = writer.close().unwrap();
+        assert_eq!(metadata.version, 2);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn bloom_filter_flags_are_applied_to_the_writer() {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let mut opts = parquet_opts();
+        opts.bloom_filter = true;
+        opts.bloom_filter_fpp = Some(0.01);
+        opts.bloom_filter_ndv = Some(1_000);
+        opts.bloom_filter_column = vec!["a".to_string()];
+
+        let path = std::env::temp_dir().join("csv2parquet_test_bloom_filter.parquet");
+        let writer = opts
+            .try_new_writer(Output::create(&path).unwrap(), schema)
+            .unwrap();
+        opts.close(writer).unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    fn csv_opts_with_projection(projection: Vec<&str>) -> CsvOpts {
+        CsvOpts {
+            header: None,
+            delimiter: ',',
+            quote: None,
+            escape: None,
+            null_regex: None,
+            projection: projection.into_iter().map(String::from).collect(),
+        }
+    }
+
+    fn csv_opts() -> CsvOpts {
+        csv_opts_with_projection(vec![])
+    }
+
+    #[test]
+    fn matching_csv_schemas_are_detected_as_equal() {
+        let opts = csv_opts();
+        let mut first = Input::Stdin(Cursor::new(b"a,b\n1,2\n".to_vec()));
+        let mut second = Input::Stdin(Cursor::new(b"a,b\n3,4\n".to_vec()));
+
+        let schema_a = opts.infer_file_schema(None, &mut first).unwrap();
+        let schema_b = opts.infer_file_schema(None, &mut second).unwrap();
+
+        assert_eq!(schema_a, schema_b);
+    }
+
+    #[test]
+    fn differing_csv_schemas_are_detected_as_unequal() {
+        let opts = csv_opts();
+        let mut first = Input::Stdin(Cursor::new(b"a,b\n1,2\n".to_vec()));
+        let mut second = Input::Stdin(Cursor::new(b"a,b,c\n1,2,3\n".to_vec()));
+
+        let schema_a = opts.infer_file_schema(None, &mut first).unwrap();
+        let schema_b = opts.infer_file_schema(None, &mut second).unwrap();
+
+        assert_ne!(schema_a, schema_b);
+    }
+
+    #[test]
+    fn projection_reorders_and_filters_the_inferred_schema() {
+        let opts = csv_opts_with_projection(vec!["c", "a"]);
+        let mut input = Input::Stdin(Cursor::new(b"a,b,c\n1,2,3\n".to_vec()));
+
+        let schema = opts.infer_file_schema(None, &mut input).unwrap();
+        let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["c", "a"]);
+    }
+
+    #[test]
+    fn projection_resolves_to_the_matching_columns_in_batches() {
+        let opts = csv_opts_with_projection(vec!["c", "a"]);
+        let mut input = Input::Stdin(Cursor::new(b"a,b,c\n1,2,3\n4,5,6\n".to_vec()));
+
+        let schema = opts.infer_file_schema(None, &mut input).unwrap();
+        let reader = opts.make_reader(Arc::new(schema), None, input).unwrap();
+        let batches: Vec<_> = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(batches[0].num_columns(), 2);
+        assert_eq!(batches[0].schema().field(0).name(), "c");
+        assert_eq!(batches[0].schema().field(1).name(), "a");
+    }
+
+    #[test]
+    fn projection_rejects_unknown_column_names() {
+        let opts = csv_opts_with_projection(vec!["does-not-exist"]);
+        let mut input = Input::Stdin(Cursor::new(b"a,b,c\n1,2,3\n".to_vec()));
+
+        assert!(opts.infer_file_schema(None, &mut input).is_err());
+    }
+
+    #[test]
+    fn projection_with_schema_file_is_rejected() {
+        let opts = csv_opts_with_projection(vec!["a"]);
+        assert!(opts.validate_schema_file_compatibility().is_err());
+        assert!(csv_opts().validate_schema_file_compatibility().is_ok());
+    }
+
+    #[test]
+    fn buffered_stdin_input_is_reusable_after_schema_inference() {
+        // Regression test: stdin can only be drained once, so the same `Input`
+        // (not a fresh one) must be seekable back to the start and readable
+        // after `infer_file_schema` has already consumed it once.
+        let opts = csv_opts();
+        let mut input = Input::Stdin(Cursor::new(b"a,b\n1,2\n3,4\n".to_vec()));
+
+        let schema = opts.infer_file_schema(None, &mut input).unwrap();
+        assert_eq!(schema.fields().len(), 2);
+
+        let reader = opts.make_reader(Arc::new(schema), None, input).unwrap();
+        let batches: Vec<_> = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(total_rows, 2);
+    }
+
+    #[test]
+    fn projection_honors_max_read_records_like_the_initial_inference() {
+        // The projection path in `make_reader` re-infers the full (unprojected)
+        // schema to resolve column indices; it must sample the same number of
+        // records as the original inference, or a column sampled as Utf8 there
+        // (zero records read) could come back as Int64 here and disagree with
+        // the writer's schema.
+        let opts = csv_opts_with_projection(vec!["a"]);
+        let mut input = Input::Stdin(Cursor::new(b"a\n1\n2\n".to_vec()));
+
+        let schema = opts.infer_file_schema(Some(0), &mut input).unwrap();
+        let reader = opts
+            .make_reader(Arc::new(schema.clone()), Some(0), input)
+            .unwrap();
+        let batches: Vec<_> = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(batches[0].schema().as_ref(), &schema);
+    }
+
+    #[test]
+    fn parse_compression_defaults_without_a_level() {
+        assert!(matches!(
+            parse_compression("snappy").unwrap(),
+            Compression::SNAPPY
+        ));
+        assert!(matches!(
+            parse_compression("uncompressed").unwrap(),
+            Compression::UNCOMPRESSED
+        ));
+        assert!(matches!(
+            parse_compression("zstd").unwrap(),
+            Compression::ZSTD(level) if level == ZstdLevel::default()
+        ));
+    }
+
+    #[test]
+    fn parse_compression_accepts_a_level_suffix() {
+        match parse_compression("zstd(9)").unwrap() {
+            Compression::ZSTD(level) => assert_eq!(level, ZstdLevel::try_new(9).unwrap()),
+            other => panic!("expected ZSTD, got {:?}", other),
+        }
+
+        match parse_compression("gzip(6)").unwrap() {
+            Compression::GZIP(level) => assert_eq!(level, GzipLevel::try_new(6).unwrap()),
+            other => panic!("expected GZIP, got {:?}", other),
+        }
+
+        match parse_compression("brotli(11)").unwrap() {
+            Compression::BROTLI(level) => assert_eq!(level, BrotliLevel::try_new(11).unwrap()),
+            other => panic!("expected BROTLI, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_compression_rejects_unknown_codec() {
+        assert!(parse_compression("lz99").is_err());
+    }
+
+    #[test]
+    fn parse_compression_rejects_malformed_level_syntax() {
+        assert!(parse_compression("zstd(9").is_err());
+        assert!(parse_compression("zstd(nine)").is_err());
+    }
+
+    #[test]
+    fn parse_compression_rejects_out_of_range_level() {
+        assert!(parse_compression("gzip(-1)").is_err());
+        assert!(parse_compression("gzip(100)").is_err());
+    }
+
+    #[test]
+    fn parse_column_encoding_splits_name_and_value() {
+        let (name, encoding) = parse_column_encoding("amount:DELTA_BINARY_PACKED").unwrap();
+        assert_eq!(name, "amount");
+        assert!(matches!(encoding, Encoding::DELTA_BINARY_PACKED));
+    }
+
+    #[test]
+    fn parse_column_compression_splits_name_and_value() {
+        let (name, compression) = parse_column_compression("amount:zstd(9)").unwrap();
+        assert_eq!(name, "amount");
+        assert!(matches!(compression, Compression::ZSTD(_)));
+    }
+
+    #[test]
+    fn parse_column_dictionary_splits_name_and_value() {
+        let (name, enabled) = parse_column_dictionary("amount:false").unwrap();
+        assert_eq!(name, "amount");
+        assert!(!enabled);
+    }
+
+    #[test]
+    fn parse_column_overrides_reject_missing_colon() {
+        assert!(parse_column_encoding("amount").is_err());
+        assert!(parse_column_compression("amount").is_err());
+        assert!(parse_column_dictionary("amount").is_err());
+    }
+
+    #[test]
+    fn parse_column_overrides_reject_invalid_values() {
+        assert!(parse_column_encoding("amount:NOT_AN_ENCODING").is_err());
+        assert!(parse_column_compression("amount:not_a_codec").is_err());
+        assert!(parse_column_dictionary("amount:not_a_bool").is_err());
+    }
+}